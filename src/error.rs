@@ -0,0 +1,109 @@
+/*
+ * Copyright 2015 Ben Ashford
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Error types
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read};
+
+use hyper::client::response::Response;
+use hyper::Error as HttpError;
+
+use rustc_serialize::json::{DecoderError, EncoderError, ParserError};
+
+/// An error produced by the `rs_es` crate, either returned directly by this
+/// crate or wrapped from one of the libraries it depends on.
+#[derive(Debug)]
+pub enum EsError {
+    /// A generic error made by this crate
+    EsError(String),
+
+    /// An error returned by the ElasticSearch server itself, the `String`
+    /// holds whatever could be extracted from the response body
+    EsServerError(String),
+
+    /// Miscellaneous HTTP error, e.g. connection refused
+    HttpError(HttpError),
+
+    /// An IO error, e.g. reading the response body
+    IoError(io::Error),
+
+    /// An error decoding or encoding JSON
+    JsonError(String)
+}
+
+impl fmt::Display for EsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            EsError::EsError(ref s)       => write!(f, "{}", s),
+            EsError::EsServerError(ref s) => write!(f, "{}", s),
+            EsError::HttpError(ref err)   => write!(f, "{}", err),
+            EsError::IoError(ref err)     => write!(f, "{}", err),
+            EsError::JsonError(ref s)     => write!(f, "{}", s)
+        }
+    }
+}
+
+impl Error for EsError {
+    fn description(&self) -> &str {
+        match *self {
+            EsError::EsError(ref s)       => s,
+            EsError::EsServerError(ref s) => s,
+            EsError::HttpError(ref err)   => err.description(),
+            EsError::IoError(ref err)     => err.description(),
+            EsError::JsonError(ref s)     => s
+        }
+    }
+}
+
+impl From<HttpError> for EsError {
+    fn from(err: HttpError) -> EsError {
+        EsError::HttpError(err)
+    }
+}
+
+impl From<io::Error> for EsError {
+    fn from(err: io::Error) -> EsError {
+        EsError::IoError(err)
+    }
+}
+
+impl From<ParserError> for EsError {
+    fn from(err: ParserError) -> EsError {
+        EsError::JsonError(format!("{}", err))
+    }
+}
+
+impl From<EncoderError> for EsError {
+    fn from(err: EncoderError) -> EsError {
+        EsError::JsonError(format!("{}", err))
+    }
+}
+
+impl From<DecoderError> for EsError {
+    fn from(err: DecoderError) -> EsError {
+        EsError::JsonError(format!("{}", err))
+    }
+}
+
+impl<'a> From<&'a mut Response> for EsError {
+    fn from(resp: &'a mut Response) -> EsError {
+        let mut body = String::new();
+        let _ = resp.read_to_string(&mut body);
+        EsError::EsServerError(format!("{}: {}", resp.status, body))
+    }
+}
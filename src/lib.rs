@@ -27,6 +27,7 @@
 
 #[macro_use]
 extern crate log;
+extern crate flate2;
 extern crate hyper;
 extern crate rustc_serialize;
 
@@ -38,19 +39,32 @@ pub mod operations;
 pub mod query;
 pub mod units;
 
+use std::io::{Read, Write};
+
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+
+use hyper::client::RequestBuilder;
+use hyper::header::{AcceptEncoding, Authorization, Basic, Bearer, ContentEncoding, Encoding, qitem};
+#[cfg(feature = "https")]
+use hyper::net::{HttpsConnector, OpensslClient};
 use hyper::status::StatusCode;
 
 use rustc_serialize::Encodable;
 use rustc_serialize::json::{self, Json};
 
 use error::EsError;
-use operations::bulk::{BulkOperation, Action};
+use operations::bulk::{BulkOperation, BulkIndexer, Action};
 use operations::delete::{DeleteOperation, DeleteByQueryOperation};
 use operations::get::GetOperation;
 use operations::index::IndexOperation;
 use operations::search::{SearchURIOperation, SearchQueryOperation};
 use operations::RefreshOperation;
 use operations::analyze::AnalyzeOperation;
+use operations::indices::{CreateIndexOperation, PutMappingOperation};
+use operations::alias::{AliasAction, GetAliasesOperation, PutAliasOperation, DeleteAliasOperation,
+                         UpdateAliasesOperation};
 
 // The client
 
@@ -63,14 +77,24 @@ use operations::analyze::AnalyzeOperation;
 pub fn do_req(resp: &mut hyper::client::response::Response)
               -> Result<(StatusCode, Option<Json>), EsError> {
     info!("Response: {:?}", resp);
+    let gzipped = resp.headers.get::<ContentEncoding>()
+        .map(|&ContentEncoding(ref encodings)| encodings.contains(&Encoding::Gzip))
+        .unwrap_or(false);
     match resp.status {
         StatusCode::Ok |
         StatusCode::Created |
-        StatusCode::NotFound => match Json::from_reader(resp) {
-            Ok(json) => Ok((resp.status, Some(json))),
-            Err(e)   => Err(EsError::from(e))
+        StatusCode::NotFound => {
+            let parsed = if gzipped {
+                Json::from_reader(&mut GzDecoder::new(&mut *resp))
+            } else {
+                Json::from_reader(&mut *resp)
+            };
+            match parsed {
+                Ok(json) => Ok((resp.status, Some(json))),
+                Err(e)   => Err(EsError::from(e))
+            }
         },
-        _                    => Err(EsError::from(resp))
+        _ => Err(EsError::from(resp))
     }
 }
 
@@ -103,7 +127,46 @@ pub fn do_req(resp: &mut hyper::client::response::Response)
 /// See the specific operations and their builder objects for details.
 pub struct Client {
     base_url:    String,
-    http_client: hyper::Client
+    http_client: hyper::Client,
+    auth:        Option<Auth>,
+    api_version: Option<ApiVersion>,
+    compression: bool
+}
+
+/// The major version of the ElasticSearch server a `Client` is talking to,
+/// determined once (and cached) by parsing the major number out of the `/`
+/// response. Operations that changed between major versions (e.g.
+/// `delete_by_query`'s endpoint, or the removal of `_ttl`) branch on this.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ApiVersion {
+    V1,
+    V2,
+    V5,
+    V6,
+    V7
+}
+
+impl ApiVersion {
+    fn from_major(major: u32) -> ApiVersion {
+        match major {
+            0 | 1 => ApiVersion::V1,
+            2 | 3 | 4 => ApiVersion::V2,
+            5 => ApiVersion::V5,
+            6 => ApiVersion::V6,
+            _ => ApiVersion::V7
+        }
+    }
+}
+
+/// How a `Client` authenticates itself to the ElasticSearch server, carried
+/// on every request issued by the `es_op!`/`es_body_op!` macros
+#[derive(Debug, Clone)]
+pub enum Auth {
+    /// HTTP Basic authentication
+    Basic { username: String, password: String },
+
+    /// A static bearer/API-key token, sent as `Authorization: Bearer <token>`
+    Bearer(String)
 }
 
 /// Create a HTTP function for the given method (GET/PUT/POST/DELETE)
@@ -113,7 +176,8 @@ macro_rules! es_op {
               -> Result<(StatusCode, Option<Json>), EsError> {
             info!("Doing {} on {}", stringify!($n), url);
             let url = self.full_url(url);
-            let mut result = try!(self.http_client.$cn(&url).send());
+            let request = self.with_common_headers(self.http_client.$cn(&url));
+            let mut result = try!(request.send());
             do_req(&mut result)
         }
     }
@@ -130,10 +194,13 @@ macro_rules! es_body_op {
                 let json_string = try!(json::encode(body));
                 info!("Body: {}", json_string);
                 let url = self.full_url(url);
-                let mut result = try!(self.http_client
-                                      .$cn(&url)
-                                      .body(&json_string)
-                                      .send());
+                let request = self.with_common_headers(self.http_client.$cn(&url));
+                let mut result = if self.compression {
+                    let compressed = Client::gzip(json_string.as_bytes());
+                    try!(request.header(ContentEncoding(vec![Encoding::Gzip])).body(&compressed[..]).send())
+                } else {
+                    try!(request.body(&json_string).send())
+                };
 
                 do_req(&mut result)
             }
@@ -141,20 +208,96 @@ macro_rules! es_body_op {
 }
 
 impl Client {
-    /// Create a new client
+    /// Create a new client, connecting over plain HTTP with no authentication
     pub fn new(host: &str, port: u32) -> Client {
+        Client::new_with_options(host, port, false, None)
+    }
+
+    /// Create a new client, optionally over HTTPS and/or with credentials to
+    /// send on every request.
+    ///
+    /// Use this to talk to a cluster that sits behind TLS and/or HTTP Basic
+    /// or bearer-token authentication.
+    pub fn new_with_options(host: &str, port: u32, https: bool, auth: Option<Auth>) -> Client {
+        let scheme = if https { "https" } else { "http" };
+        let http_client = if https {
+            Client::https_client()
+        } else {
+            hyper::Client::new()
+        };
         Client {
-            base_url:    format!("http://{}:{}", host, port),
-            http_client: hyper::Client::new()
+            base_url:    format!("{}://{}:{}", scheme, host, port),
+            http_client: http_client,
+            auth:        auth,
+            api_version: None,
+            compression: false
         }
     }
 
+    /// Build a TLS-capable `hyper::Client`; only available with the `https`
+    /// Cargo feature, which pulls in hyper's `ssl` feature (and
+    /// transitively `openssl-sys`) - not something plain-HTTP users should
+    /// have to link against.
+    #[cfg(feature = "https")]
+    fn https_client() -> hyper::Client {
+        hyper::Client::with_connector(HttpsConnector::new(OpensslClient::default()))
+    }
+
+    #[cfg(not(feature = "https"))]
+    fn https_client() -> hyper::Client {
+        panic!("rs_es was built without the `https` Cargo feature; enable it to connect over HTTPS")
+    }
+
+    /// Gzip-compress request bodies before sending them (and advertise
+    /// `Accept-Encoding: gzip` so the server may compress its responses in
+    /// turn). Off by default; worth enabling for large payloads such as
+    /// `bulk` ingestion.
+    pub fn with_compression(&mut self, enabled: bool) -> &mut Self {
+        self.compression = enabled;
+        self
+    }
+
+    /// gzip-compress a request body, to be sent with a `Content-Encoding:
+    /// gzip` header
+    fn gzip(body: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(body).expect("Writing to an in-memory buffer cannot fail");
+        encoder.finish().expect("Writing to an in-memory buffer cannot fail")
+    }
+
     /// Take a nearly complete ElasticSearch URL, and stick the host/port part
     /// on the front.
     pub fn full_url(&self, suffix: &str) -> String {
         format!("{}/{}", self.base_url, suffix)
     }
 
+    /// Add this client's `Auth`, if any, to a request as the appropriate
+    /// `Authorization` header
+    fn with_auth<'c>(&self, request: RequestBuilder<'c>) -> RequestBuilder<'c> {
+        match self.auth {
+            Some(Auth::Basic { ref username, ref password }) =>
+                request.header(Authorization(Basic {
+                    username: username.clone(),
+                    password: Some(password.clone())
+                })),
+            Some(Auth::Bearer(ref token)) =>
+                request.header(Authorization(Bearer { token: token.clone() })),
+            None => request
+        }
+    }
+
+    /// Add this client's `Auth` (if any) and, when compression is enabled,
+    /// an `Accept-Encoding: gzip` header so the server may compress its
+    /// response
+    fn with_common_headers<'c>(&self, request: RequestBuilder<'c>) -> RequestBuilder<'c> {
+        let request = self.with_auth(request);
+        if self.compression {
+            request.header(AcceptEncoding(vec![qitem(Encoding::Gzip)]))
+        } else {
+            request
+        }
+    }
+
     es_op!(get_op, get);
 
     es_op!(post_op, post);
@@ -164,6 +307,24 @@ impl Client {
     es_op!(delete_op, delete);
     es_body_op!(delete_body_op, delete);
 
+    /// Like `post_body_op`, but sends `body` as-is rather than JSON-encoding
+    /// it; used by operations such as `bulk` that build their own
+    /// newline-delimited JSON payload.
+    fn post_raw_body_op(&mut self, url: &str, body: &str)
+                         -> Result<(StatusCode, Option<Json>), EsError> {
+        info!("Doing post_raw_body_op on {}", url);
+        info!("Body: {}", body);
+        let url = self.full_url(url);
+        let request = self.with_common_headers(self.http_client.post(&url));
+        let mut result = if self.compression {
+            let compressed = Client::gzip(body.as_bytes());
+            try!(request.header(ContentEncoding(vec![Encoding::Gzip])).body(&compressed[..]).send())
+        } else {
+            try!(request.body(body).send())
+        };
+        do_req(&mut result)
+    }
+
     /// Calls the base ES path, returning the version number
     pub fn version(&mut self) -> Result<String, EsError> {
         let (_, result) = try!(self.get_op("/"));
@@ -179,6 +340,23 @@ impl Client {
         }
     }
 
+    /// The server's `ApiVersion`, determined from `version` and cached for
+    /// the lifetime of this `Client` so operations can cheaply branch on it.
+    pub fn api_version(&mut self) -> Result<ApiVersion, EsError> {
+        if let Some(api_version) = self.api_version {
+            return Ok(api_version);
+        }
+        let version = try!(self.version());
+        let major = match version.split('.').next().and_then(|s| s.parse::<u32>().ok()) {
+            Some(major) => major,
+            None        => return Err(EsError::EsError(format!("Cannot parse major version from: {}",
+                                                                version)))
+        };
+        let api_version = ApiVersion::from_major(major);
+        self.api_version = Some(api_version);
+        Ok(api_version)
+    }
+
     // Indices APIs
 
     /// Refresh
@@ -188,6 +366,46 @@ impl Client {
         RefreshOperation::new(self)
     }
 
+    /// Create an index, optionally with custom settings and mappings
+    ///
+    /// See: https://www.elastic.co/guide/en/elasticsearch/reference/current/indices-create-index.html
+    pub fn create_index<'a>(&'a mut self, index: &'a str) -> CreateIndexOperation<'a> {
+        CreateIndexOperation::new(self, index)
+    }
+
+    /// Add (or update) the mapping of an existing index
+    ///
+    /// See: https://www.elastic.co/guide/en/elasticsearch/reference/current/indices-put-mapping.html
+    pub fn put_mapping<'a>(&'a mut self, index: &'a str, doc_type: &'a str) -> PutMappingOperation<'a> {
+        PutMappingOperation::new(self, index, doc_type)
+    }
+
+    /// Get the aliases for one or more indices
+    ///
+    /// See: https://www.elastic.co/guide/en/elasticsearch/reference/current/indices-get-aliases.html
+    pub fn get_aliases<'a>(&'a mut self) -> GetAliasesOperation<'a> {
+        GetAliasesOperation::new(self)
+    }
+
+    /// Add a single alias to a single index
+    ///
+    /// See: https://www.elastic.co/guide/en/elasticsearch/reference/current/indices-aliases.html
+    pub fn put_alias<'a>(&'a mut self, index: &'a str, alias: &'a str) -> PutAliasOperation<'a> {
+        PutAliasOperation::new(self, index, alias)
+    }
+
+    /// Remove a single alias from a single index
+    pub fn delete_alias<'a>(&'a mut self, index: &'a str, alias: &'a str) -> DeleteAliasOperation<'a> {
+        DeleteAliasOperation::new(self, index, alias)
+    }
+
+    /// Atomically apply a batch of add/remove alias actions
+    ///
+    /// See: https://www.elastic.co/guide/en/elasticsearch/reference/current/indices-aliases.html
+    pub fn update_aliases<'a>(&'a mut self, actions: Vec<AliasAction>) -> UpdateAliasesOperation<'a> {
+        UpdateAliasesOperation::new(self, actions)
+    }
+
     // Document APIs
 
     /// An index operation to index a document in the specified index.
@@ -223,7 +441,9 @@ impl Client {
     ///
     /// See: https://www.elastic.co/guide/en/elasticsearch/reference/1.x/docs-delete-by-query.html
     ///
-    /// Warning: will be removed in ElasticSearch 2.0
+    /// Removed from ElasticSearch core in 2.0 and reintroduced as the
+    /// `_delete_by_query` endpoint; picks the right URL and body/response
+    /// shape based on the server's `ApiVersion`, detected via `api_version`.
     pub fn delete_by_query<'a>(&'a mut self) -> DeleteByQueryOperation {
         DeleteByQueryOperation::new(self)
     }
@@ -235,6 +455,15 @@ impl Client {
         BulkOperation::new(self, actions)
     }
 
+    /// A streaming bulk-ingestion helper that buffers pushed `Action`s and
+    /// automatically flushes them in batches, for ingesting more documents
+    /// than can comfortably be held in memory at once
+    ///
+    /// See: https://www.elastic.co/guide/en/elasticsearch/reference/current/docs-bulk.html
+    pub fn bulk_stream<'a>(&'a mut self) -> BulkIndexer<'a> {
+        BulkIndexer::new(self)
+    }
+
     /// Analyze
     ///
     /// See: https://www.elastic.co/guide/en/elasticsearch/reference/current/indices-analyze.html
@@ -260,7 +489,51 @@ impl Client {
     }
 }
 
+#[cfg(all(test, feature = "https"))]
+mod https_tests {
+    use super::Client;
+
+    #[test]
+    fn https_client_uses_a_tls_capable_connector() {
+        // Nothing listens on port 1, so this never reaches a real server;
+        // the point is only to exercise the connector `new_with_options`
+        // wires up for `https=true`. Before this connector was TLS-capable,
+        // hyper's plain `HttpConnector` rejected the `https` scheme outright
+        // with an `Io(InvalidInput, "Invalid scheme for Http")` error before
+        // ever touching the network - so seeing anything else here (e.g. a
+        // connection refused) proves the https path is actually wired up.
+        let mut client = Client::new_with_options("localhost", 1, true, None);
+        let err = client.get("any_index", "1").send().unwrap_err();
+        let message = err.to_string();
+        assert!(!message.contains("Invalid scheme"),
+                "https client rejected its own scheme: {}", message);
+    }
+}
+
 #[cfg(test)]
+mod compression_tests {
+    use std::io::Read;
+
+    use flate2::read::GzDecoder;
+
+    use super::Client;
+
+    #[test]
+    fn gzip_roundtrips_back_to_the_original_body() {
+        let body = b"the quick brown fox jumps over the lazy dog";
+        let compressed = Client::gzip(body);
+        assert_ne!(&compressed[..], &body[..]);
+
+        let mut decompressed = Vec::new();
+        GzDecoder::new(&compressed[..]).read_to_end(&mut decompressed).unwrap();
+        assert_eq!(&decompressed[..], &body[..]);
+    }
+}
+
+// Talks to a live ElasticSearch server; opt in with
+// `--features network-tests` against a running `ES_HOST` (default
+// "localhost").
+#[cfg(all(test, feature = "network-tests"))]
 pub mod tests {
     extern crate env_logger;
     extern crate regex;
@@ -268,6 +541,7 @@ pub mod tests {
     use std::collections::BTreeMap;
     use std::env;
 
+    use rustc_serialize::{Decodable, Decoder, Encodable, Encoder};
     use rustc_serialize::json::{Json, ToJson};
 
     use super::Client;
@@ -288,12 +562,35 @@ pub mod tests {
         Client::new(&hostname, 9200)
     }
 
-    #[derive(Debug, RustcDecodable, RustcEncodable)]
+    #[derive(Debug)]
     pub struct TestDocument {
         pub str_field: String,
         pub int_field: i64
     }
 
+    // The `RustcDecodable`/`RustcEncodable` derives relied on a
+    // compiler-plugin expansion that no longer exists on any current
+    // `rustc`, so these are implemented by hand instead.
+    impl Encodable for TestDocument {
+        fn encode<S: Encoder>(&self, s: &mut S) -> Result<(), S::Error> {
+            s.emit_struct("TestDocument", 2, |s| {
+                try!(s.emit_struct_field("str_field", 0, |s| self.str_field.encode(s)));
+                try!(s.emit_struct_field("int_field", 1, |s| self.int_field.encode(s)));
+                Ok(())
+            })
+        }
+    }
+
+    impl Decodable for TestDocument {
+        fn decode<D: Decoder>(d: &mut D) -> Result<TestDocument, D::Error> {
+            d.read_struct("TestDocument", 2, |d| {
+                let str_field = try!(d.read_struct_field("str_field", 0, |d| Decodable::decode(d)));
+                let int_field = try!(d.read_struct_field("int_field", 1, |d| Decodable::decode(d)));
+                Ok(TestDocument { str_field: str_field, int_field: int_field })
+            })
+        }
+    }
+
     impl TestDocument {
         pub fn new() -> TestDocument {
             TestDocument {
@@ -0,0 +1,209 @@
+/*
+ * Copyright 2015 Ben Ashford
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Index alias management, including atomic add/remove swaps, see:
+//! https://www.elastic.co/guide/en/elasticsearch/reference/current/indices-aliases.html
+
+use std::collections::BTreeMap;
+
+use rustc_serialize::json::{Json, ToJson};
+
+use ::Client;
+use error::EsError;
+
+/// A single action within an `update_aliases` batch
+pub enum AliasAction {
+    Add    { index: String, alias: String },
+    Remove { index: String, alias: String }
+}
+
+impl AliasAction {
+    pub fn add<S: Into<String>>(index: S, alias: S) -> AliasAction {
+        AliasAction::Add { index: index.into(), alias: alias.into() }
+    }
+
+    pub fn remove<S: Into<String>>(index: S, alias: S) -> AliasAction {
+        AliasAction::Remove { index: index.into(), alias: alias.into() }
+    }
+}
+
+impl ToJson for AliasAction {
+    fn to_json(&self) -> Json {
+        let (name, index, alias) = match *self {
+            AliasAction::Add    { ref index, ref alias } => ("add", index, alias),
+            AliasAction::Remove { ref index, ref alias } => ("remove", index, alias)
+        };
+        let mut inner = BTreeMap::new();
+        inner.insert("index".to_owned(), index.to_json());
+        inner.insert("alias".to_owned(), alias.to_json());
+        let mut d = BTreeMap::new();
+        d.insert(name.to_owned(), Json::Object(inner));
+        Json::Object(d)
+    }
+}
+
+/// Get the aliases for one or more indices
+///
+/// See: https://www.elastic.co/guide/en/elasticsearch/reference/current/indices-get-aliases.html
+pub struct GetAliasesOperation<'a> {
+    client: &'a mut Client,
+    index:  Option<&'a str>
+}
+
+impl<'a> GetAliasesOperation<'a> {
+    pub fn new(client: &'a mut Client) -> GetAliasesOperation<'a> {
+        GetAliasesOperation { client: client, index: None }
+    }
+
+    pub fn with_index(&mut self, index: &'a str) -> &mut Self {
+        self.index = Some(index);
+        self
+    }
+
+    pub fn send(&mut self) -> Result<GetAliasesResult, EsError> {
+        let url = match self.index {
+            Some(index) => format!("{}/_alias", index),
+            None        => "_alias".to_owned()
+        };
+        let (_, result) = try!(self.client.get_op(&url));
+        let result = result.expect("No Json payload");
+        GetAliasesResult::from(&result)
+    }
+}
+
+/// The result of a `get_aliases` call: the set of alias names registered
+/// against each index
+#[derive(Debug)]
+pub struct GetAliasesResult {
+    indices: BTreeMap<String, Vec<String>>
+}
+
+impl GetAliasesResult {
+    fn from(json: &Json) -> Result<GetAliasesResult, EsError> {
+        let mut indices = BTreeMap::new();
+        if let Some(obj) = json.as_object() {
+            for (index, body) in obj {
+                let aliases = body.find("aliases")
+                    .and_then(|v| v.as_object())
+                    .map(|aliases| aliases.keys().cloned().collect())
+                    .unwrap_or_else(Vec::new);
+                indices.insert(index.clone(), aliases);
+            }
+        }
+        Ok(GetAliasesResult { indices: indices })
+    }
+
+    /// The alias names registered against the given index, or an empty
+    /// slice if it has none (or wasn't present in the response)
+    pub fn aliases_for(&self, index: &str) -> &[String] {
+        self.indices.get(index).map(|v| &v[..]).unwrap_or(&[])
+    }
+}
+
+/// Add a single alias to a single index
+///
+/// See: https://www.elastic.co/guide/en/elasticsearch/reference/current/indices-aliases.html
+pub struct PutAliasOperation<'a> {
+    client: &'a mut Client,
+    index:  &'a str,
+    alias:  &'a str
+}
+
+impl<'a> PutAliasOperation<'a> {
+    pub fn new(client: &'a mut Client, index: &'a str, alias: &'a str) -> PutAliasOperation<'a> {
+        PutAliasOperation { client: client, index: index, alias: alias }
+    }
+
+    pub fn send(&mut self) -> Result<(), EsError> {
+        let url = format!("{}/_alias/{}", self.index, self.alias);
+        self.client.put_op(&url).map(|_| ())
+    }
+}
+
+/// Remove a single alias from a single index
+pub struct DeleteAliasOperation<'a> {
+    client: &'a mut Client,
+    index:  &'a str,
+    alias:  &'a str
+}
+
+impl<'a> DeleteAliasOperation<'a> {
+    pub fn new(client: &'a mut Client, index: &'a str, alias: &'a str) -> DeleteAliasOperation<'a> {
+        DeleteAliasOperation { client: client, index: index, alias: alias }
+    }
+
+    pub fn send(&mut self) -> Result<(), EsError> {
+        let url = format!("{}/_alias/{}", self.index, self.alias);
+        self.client.delete_op(&url).map(|_| ())
+    }
+}
+
+/// Atomically apply a batch of `AliasAction`s, e.g. to swap an alias from
+/// one index to another with no window where it points to zero or two
+/// indices
+///
+/// See: https://www.elastic.co/guide/en/elasticsearch/reference/current/indices-aliases.html
+pub struct UpdateAliasesOperation<'a> {
+    client:  &'a mut Client,
+    actions: Vec<AliasAction>
+}
+
+impl<'a> UpdateAliasesOperation<'a> {
+    pub fn new(client: &'a mut Client, actions: Vec<AliasAction>) -> UpdateAliasesOperation<'a> {
+        UpdateAliasesOperation { client: client, actions: actions }
+    }
+
+    pub fn send(&mut self) -> Result<(), EsError> {
+        let mut body = BTreeMap::new();
+        body.insert("actions".to_owned(), self.actions.to_json());
+        self.client.post_body_op("_aliases", &Json::Object(body)).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rustc_serialize::json::{Json, ToJson};
+
+    use super::{AliasAction, GetAliasesResult};
+
+    #[test]
+    fn alias_action_add_to_json() {
+        let json = AliasAction::add("my_index", "my_alias").to_json();
+        assert_eq!(json.find_path(&["add", "index"]).and_then(|v| v.as_string()), Some("my_index"));
+        assert_eq!(json.find_path(&["add", "alias"]).and_then(|v| v.as_string()), Some("my_alias"));
+    }
+
+    #[test]
+    fn alias_action_remove_to_json() {
+        let json = AliasAction::remove("my_index", "my_alias").to_json();
+        assert_eq!(json.find_path(&["remove", "index"]).and_then(|v| v.as_string()), Some("my_index"));
+        assert_eq!(json.find_path(&["remove", "alias"]).and_then(|v| v.as_string()), Some("my_alias"));
+    }
+
+    #[test]
+    fn get_aliases_result_from_collects_alias_names_per_index() {
+        let json = Json::from_str(r#"{
+            "index1": {"aliases": {"alias1": {}, "alias2": {}}},
+            "index2": {"aliases": {}}
+        }"#).unwrap();
+        let result = GetAliasesResult::from(&json).unwrap();
+        let mut aliases = result.aliases_for("index1").to_vec();
+        aliases.sort();
+        assert_eq!(aliases, vec!["alias1".to_owned(), "alias2".to_owned()]);
+        assert!(result.aliases_for("index2").is_empty());
+        assert!(result.aliases_for("missing").is_empty());
+    }
+}
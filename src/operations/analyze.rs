@@ -0,0 +1,57 @@
+/*
+ * Copyright 2015 Ben Ashford
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Implementation of the ElasticSearch Analyze API, see:
+//! https://www.elastic.co/guide/en/elasticsearch/reference/current/indices-analyze.html
+
+use std::collections::BTreeMap;
+
+use rustc_serialize::json::Json;
+
+use ::Client;
+use error::EsError;
+
+/// Analyze a piece of text with the default (or a named) analyzer
+pub struct AnalyzeOperation<'a> {
+    client:   &'a mut Client,
+    body:     &'a str,
+    analyzer: Option<&'a str>
+}
+
+impl<'a> AnalyzeOperation<'a> {
+    pub fn new(client: &'a mut Client, body: &'a str) -> AnalyzeOperation<'a> {
+        AnalyzeOperation {
+            client:   client,
+            body:     body,
+            analyzer: None
+        }
+    }
+
+    pub fn with_analyzer(&mut self, analyzer: &'a str) -> &mut Self {
+        self.analyzer = Some(analyzer);
+        self
+    }
+
+    pub fn send(&mut self) -> Result<Json, EsError> {
+        let mut body = BTreeMap::new();
+        body.insert("text".to_owned(), Json::String(self.body.to_owned()));
+        if let Some(analyzer) = self.analyzer {
+            body.insert("analyzer".to_owned(), Json::String(analyzer.to_owned()));
+        }
+        let (_, result) = try!(self.client.post_body_op("_analyze", &Json::Object(body)));
+        Ok(result.expect("No Json payload"))
+    }
+}
@@ -0,0 +1,412 @@
+/*
+ * Copyright 2015 Ben Ashford
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Implementation of the ElasticSearch Bulk API, see:
+//! https://www.elastic.co/guide/en/elasticsearch/reference/current/docs-bulk.html
+
+use std::collections::BTreeMap;
+use std::mem;
+
+use rustc_serialize::json::{Json, ToJson};
+
+use ::{ApiVersion, Client};
+use error::EsError;
+use operations::doc_type_segment;
+
+enum ActionOp {
+    Index(Json),
+    Create(Json),
+    Update(Json),
+    Delete
+}
+
+/// A single action within a bulk request
+pub struct Action {
+    op: ActionOp,
+    id: Option<String>
+}
+
+impl Action {
+    fn new(op: ActionOp) -> Action {
+        Action { op: op, id: None }
+    }
+
+    /// Index (or overwrite) a document
+    pub fn index<E: ToJson>(doc: E) -> Action {
+        Action::new(ActionOp::Index(doc.to_json()))
+    }
+
+    /// Create a document, failing if one already exists with the same ID
+    pub fn create<E: ToJson>(doc: E) -> Action {
+        Action::new(ActionOp::Create(doc.to_json()))
+    }
+
+    /// Partially update a document, merging `doc` into the existing source
+    pub fn update<E: ToJson>(doc: E) -> Action {
+        Action::new(ActionOp::Update(doc.to_json()))
+    }
+
+    /// Delete a document, which must be given an ID via `with_id`
+    pub fn delete() -> Action {
+        Action::new(ActionOp::Delete)
+    }
+
+    /// Set the document ID this action applies to; required for `update`
+    /// and `delete`, optional (ElasticSearch will generate one) for `index`
+    /// and `create`
+    pub fn with_id<S: Into<String>>(mut self, id: S) -> Action {
+        self.id = Some(id.into());
+        self
+    }
+
+    fn header_name(&self) -> &'static str {
+        match self.op {
+            ActionOp::Index(_)  => "index",
+            ActionOp::Create(_) => "create",
+            ActionOp::Update(_) => "update",
+            ActionOp::Delete     => "delete"
+        }
+    }
+
+    /// Render the `{action}\n{source}\n` pair of lines used by the NDJSON
+    /// bulk body; a `delete` action renders only the `{action}\n` line
+    pub fn to_lines(&self, api_version: ApiVersion, index: Option<&str>, doc_type: Option<&str>) -> String {
+        let mut meta = BTreeMap::new();
+        if let Some(index) = index {
+            meta.insert("_index".to_owned(), Json::String(index.to_owned()));
+        }
+        if let Some(doc_type) = doc_type {
+            meta.insert("_type".to_owned(), Json::String(doc_type_segment(api_version, doc_type)));
+        }
+        if let Some(ref id) = self.id {
+            meta.insert("_id".to_owned(), Json::String(id.clone()));
+        }
+        let mut header = BTreeMap::new();
+        header.insert(self.header_name().to_owned(), Json::Object(meta));
+
+        let mut lines = Json::Object(header).to_string();
+        lines.push('\n');
+        match self.op {
+            ActionOp::Index(ref doc) | ActionOp::Create(ref doc) => {
+                lines.push_str(&doc.to_string());
+                lines.push('\n');
+            },
+            ActionOp::Update(ref doc) => {
+                let mut body = BTreeMap::new();
+                body.insert("doc".to_owned(), doc.clone());
+                lines.push_str(&Json::Object(body).to_string());
+                lines.push('\n');
+            },
+            ActionOp::Delete => {}
+        }
+        lines
+    }
+}
+
+/// A bulk operation, submitting a fixed slice of `Action`s in a single
+/// request
+pub struct BulkOperation<'a, 'b> {
+    client:   &'a mut Client,
+    actions:  &'b [Action],
+    index:    Option<&'b str>,
+    doc_type: Option<&'b str>
+}
+
+impl<'a, 'b> BulkOperation<'a, 'b> {
+    pub fn new(client: &'a mut Client, actions: &'b [Action]) -> BulkOperation<'a, 'b> {
+        BulkOperation {
+            client:   client,
+            actions:  actions,
+            index:    None,
+            doc_type: None
+        }
+    }
+
+    pub fn with_index(&mut self, index: &'b str) -> &mut Self {
+        self.index = Some(index);
+        self
+    }
+
+    pub fn with_doc_type(&mut self, doc_type: &'b str) -> &mut Self {
+        self.doc_type = Some(doc_type);
+        self
+    }
+
+    fn body(&self, api_version: ApiVersion) -> String {
+        self.actions.iter()
+            .map(|action| action.to_lines(api_version, self.index, self.doc_type))
+            .collect::<Vec<_>>()
+            .concat()
+    }
+
+    fn url(&self, api_version: ApiVersion) -> String {
+        match (self.index, self.doc_type) {
+            (Some(index), Some(doc_type)) => {
+                format!("{}/{}/_bulk", index, doc_type_segment(api_version, doc_type))
+            },
+            (Some(index), None)           => format!("{}/_bulk", index),
+            _                              => "_bulk".to_owned()
+        }
+    }
+
+    pub fn send(&mut self) -> Result<BulkResult, EsError> {
+        let api_version = try!(self.client.api_version());
+        let url = self.url(api_version);
+        let body = self.body(api_version);
+        let (_, result) = try!(self.client.post_raw_body_op(&url, &body));
+        let result = result.expect("No Json payload");
+        BulkResult::from(&result)
+    }
+}
+
+#[derive(Debug)]
+pub struct BulkResult {
+    pub errors: bool,
+    pub items:  Vec<Json>
+}
+
+impl BulkResult {
+    fn from(json: &Json) -> Result<BulkResult, EsError> {
+        Ok(BulkResult {
+            errors: json.find("errors").and_then(|v| v.as_boolean()).unwrap_or(false),
+            items:  json.find("items")
+                .and_then(|v| v.as_array())
+                .map(|items| items.clone())
+                .unwrap_or_default()
+        })
+    }
+
+    /// The per-item results that report a failure, i.e. whose action (the
+    /// single key of each item object) carries an `error` field
+    fn failed_items(&self) -> Vec<Json> {
+        self.items.iter().filter_map(|item| {
+            item.as_object()
+                .and_then(|obj| obj.values().next())
+                .and_then(|action_result| action_result.find("error"))
+                .map(|_| item.clone())
+        }).collect()
+    }
+}
+
+/// The default number of buffered actions that triggers an automatic flush
+pub const DEFAULT_MAX_ACTIONS: usize = 1000;
+
+/// The default buffered body size (in bytes) that triggers an automatic
+/// flush
+pub const DEFAULT_MAX_BYTES: usize = 5 * 1024 * 1024;
+
+/// A streaming bulk-ingestion helper: actions are pushed in one at a time
+/// and automatically flushed in batches, rather than requiring the caller
+/// to materialize the whole set of actions (and the NDJSON body) up front.
+///
+/// ```no_run
+/// use rs_es::Client;
+/// use rs_es::operations::bulk::Action;
+///
+/// let mut client = Client::new("localhost", 9200);
+/// let mut indexer = client.bulk_stream();
+/// indexer.with_index("my_index").with_doc_type("my_type");
+/// for i in 0..1_000_000 {
+///     indexer.push(Action::index(i)).unwrap();
+/// }
+/// let result = indexer.close().unwrap();
+/// println!("indexed with {} failures", result.errors.len());
+/// ```
+pub struct BulkIndexer<'a> {
+    client:      &'a mut Client,
+    index:       Option<String>,
+    doc_type:    Option<String>,
+    max_actions: usize,
+    max_bytes:   usize,
+    buffer:      Vec<Action>,
+    buffer_size: usize,
+    result:      BulkIndexerResult
+}
+
+/// The aggregated outcome of a `BulkIndexer`, accumulated across every
+/// `flush` (automatic or final)
+#[derive(Debug, Default)]
+pub struct BulkIndexerResult {
+    /// The total number of actions that have been flushed so far
+    pub sent:   usize,
+    /// Every per-item result that reported an `error`, across all flushes
+    pub errors: Vec<Json>
+}
+
+impl<'a> BulkIndexer<'a> {
+    pub fn new(client: &'a mut Client) -> BulkIndexer<'a> {
+        BulkIndexer {
+            client:      client,
+            index:       None,
+            doc_type:    None,
+            max_actions: DEFAULT_MAX_ACTIONS,
+            max_bytes:   DEFAULT_MAX_BYTES,
+            buffer:      vec![],
+            buffer_size: 0,
+            result:      Default::default()
+        }
+    }
+
+    pub fn with_index<S: Into<String>>(&mut self, index: S) -> &mut Self {
+        self.index = Some(index.into());
+        self
+    }
+
+    pub fn with_doc_type<S: Into<String>>(&mut self, doc_type: S) -> &mut Self {
+        self.doc_type = Some(doc_type.into());
+        self
+    }
+
+    /// Flush automatically once this many actions are buffered
+    pub fn with_max_actions(&mut self, max_actions: usize) -> &mut Self {
+        self.max_actions = max_actions;
+        self
+    }
+
+    /// Flush automatically once the buffered NDJSON body reaches this many
+    /// bytes
+    pub fn with_max_bytes(&mut self, max_bytes: usize) -> &mut Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Buffer an action, automatically flushing if that crosses either
+    /// threshold
+    pub fn push(&mut self, action: Action) -> Result<(), EsError> {
+        // `max_bytes` is a heuristic for when to flush, not a wire-accurate
+        // count, so estimate it without asking the server for its
+        // `ApiVersion` here; the exact version-appropriate rendering happens
+        // in `flush`, via `BulkOperation::send`.
+        self.buffer_size += action.to_lines(ApiVersion::V1,
+                                            self.index.as_ref().map(|s| &s[..]),
+                                            self.doc_type.as_ref().map(|s| &s[..])).len();
+        self.buffer.push(action);
+        if self.buffer.len() >= self.max_actions || self.buffer_size >= self.max_bytes {
+            try!(self.flush());
+        }
+        Ok(())
+    }
+
+    /// Send any currently-buffered actions as a single `_bulk` request,
+    /// folding the per-item errors into the aggregated result
+    pub fn flush(&mut self) -> Result<(), EsError> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let result = {
+            let mut op = BulkOperation::new(&mut *self.client, &self.buffer);
+            if let Some(ref index) = self.index {
+                op.with_index(index);
+            }
+            if let Some(ref doc_type) = self.doc_type {
+                op.with_doc_type(doc_type);
+            }
+            try!(op.send())
+        };
+        self.result.sent += self.buffer.len();
+        self.result.errors.extend(result.failed_items());
+        self.buffer.clear();
+        self.buffer_size = 0;
+        Ok(())
+    }
+
+    /// Flush any remaining buffered actions and return the aggregated
+    /// result accumulated across every flush
+    ///
+    /// Takes `self` by reference rather than by value: if the final `flush`
+    /// fails (e.g. a transient network error), the indexer - including
+    /// whatever hadn't been flushed yet - is left intact in `self` rather
+    /// than being dropped, so the caller can retry `close` instead of
+    /// losing the unsent buffer.
+    pub fn close(&mut self) -> Result<BulkIndexerResult, EsError> {
+        try!(self.flush());
+        Ok(mem::take(&mut self.result))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rustc_serialize::json::Json;
+
+    use ::{ApiVersion, Client};
+
+    use super::{Action, BulkIndexer, BulkResult};
+
+    #[test]
+    fn action_to_lines_index_includes_index_and_type_but_no_id() {
+        let action = Action::index(42);
+        let lines = action.to_lines(ApiVersion::V1, Some("my_index"), Some("my_type"));
+        let mut it = lines.lines();
+        let header = Json::from_str(it.next().unwrap()).unwrap();
+        assert_eq!(header.find_path(&["index", "_index"]).unwrap().as_string(), Some("my_index"));
+        assert_eq!(header.find_path(&["index", "_type"]).unwrap().as_string(), Some("my_type"));
+        assert!(header.find_path(&["index", "_id"]).is_none());
+        assert_eq!(it.next(), Some("42"));
+        assert_eq!(it.next(), None);
+    }
+
+    #[test]
+    fn action_to_lines_on_v7_plus_rewrites_type_to_doc() {
+        let action = Action::index(42);
+        let lines = action.to_lines(ApiVersion::V7, Some("my_index"), Some("my_type"));
+        let header = Json::from_str(lines.lines().next().unwrap()).unwrap();
+        assert_eq!(header.find_path(&["index", "_type"]).unwrap().as_string(), Some("_doc"));
+    }
+
+    #[test]
+    fn action_to_lines_delete_with_id_has_no_body_line() {
+        let action = Action::delete().with_id("1");
+        let lines = action.to_lines(ApiVersion::V1, None, None);
+        let mut it = lines.lines();
+        let header = Json::from_str(it.next().unwrap()).unwrap();
+        assert_eq!(header.find_path(&["delete", "_id"]).unwrap().as_string(), Some("1"));
+        assert_eq!(it.next(), None);
+    }
+
+    #[test]
+    fn bulk_result_from_parses_errors_and_items() {
+        let json = Json::from_str(r#"{
+            "errors": true,
+            "items": [
+                {"index": {"_id": "1", "status": 201}},
+                {"index": {"_id": "2", "status": 409, "error": "conflict"}}
+            ]
+        }"#).unwrap();
+        let result = BulkResult::from(&json).unwrap();
+        assert!(result.errors);
+        assert_eq!(result.items.len(), 2);
+        assert_eq!(result.failed_items().len(), 1);
+    }
+
+    #[test]
+    fn bulk_result_from_defaults_when_fields_missing() {
+        let json = Json::from_str("{}").unwrap();
+        let result = BulkResult::from(&json).unwrap();
+        assert!(!result.errors);
+        assert!(result.items.is_empty());
+    }
+
+    #[test]
+    fn push_buffers_without_flushing_under_thresholds() {
+        let mut client = Client::new("localhost", 9200);
+        let mut indexer = BulkIndexer::new(&mut client);
+        indexer.with_max_actions(10).with_max_bytes(1024 * 1024);
+        indexer.push(Action::index(1)).unwrap();
+        indexer.push(Action::index(2)).unwrap();
+        assert_eq!(indexer.buffer.len(), 2);
+        assert_eq!(indexer.result.sent, 0);
+    }
+}
@@ -0,0 +1,186 @@
+/*
+ * Copyright 2015 Ben Ashford
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Implementation of the ElasticSearch Delete and Delete-By-Query APIs, see:
+//! https://www.elastic.co/guide/en/elasticsearch/reference/1.x/docs-delete.html
+//! https://www.elastic.co/guide/en/elasticsearch/reference/1.x/docs-delete-by-query.html
+
+use std::collections::BTreeMap;
+
+use rustc_serialize::json::Json;
+
+use ::{ApiVersion, Client};
+use error::EsError;
+use operations::doc_type_segment;
+use query::Query;
+use util::join_strings;
+
+/// Delete by ID
+pub struct DeleteOperation<'a> {
+    client:   &'a mut Client,
+    index:    &'a str,
+    doc_type: &'a str,
+    id:       &'a str
+}
+
+impl<'a> DeleteOperation<'a> {
+    pub fn new(client: &'a mut Client, index: &'a str, doc_type: &'a str, id: &'a str) -> DeleteOperation<'a> {
+        DeleteOperation {
+            client:   client,
+            index:    index,
+            doc_type: doc_type,
+            id:       id
+        }
+    }
+
+    pub fn send(&mut self) -> Result<(), EsError> {
+        let api_version = try!(self.client.api_version());
+        let doc_type = doc_type_segment(api_version, self.doc_type);
+        let url = format!("{}/{}/{}", self.index, doc_type, self.id);
+        self.client.delete_op(&url).map(|_| ())
+    }
+}
+
+/// Delete by query
+///
+/// Removed from ElasticSearch core in 2.0 and reintroduced as a plugin-then-
+/// core `_delete_by_query` endpoint; `send` picks the right URL and body
+/// shape based on the server's `ApiVersion`.
+pub struct DeleteByQueryOperation<'a> {
+    client:     &'a mut Client,
+    indexes:    Vec<String>,
+    doc_types:  Vec<String>,
+    query:      Option<Json>
+}
+
+impl<'a> DeleteByQueryOperation<'a> {
+    pub fn new(client: &'a mut Client) -> DeleteByQueryOperation<'a> {
+        DeleteByQueryOperation {
+            client:    client,
+            indexes:   vec![],
+            doc_types: vec![],
+            query:     None
+        }
+    }
+
+    pub fn with_indexes(&mut self, indexes: &[&str]) -> &mut Self {
+        self.indexes = indexes.iter().map(|s| (*s).to_owned()).collect();
+        self
+    }
+
+    pub fn with_doc_types(&mut self, doc_types: &[&str]) -> &mut Self {
+        self.doc_types = doc_types.iter().map(|s| (*s).to_owned()).collect();
+        self
+    }
+
+    pub fn with_query(&mut self, query: &Query) -> &mut Self {
+        self.query = Some(query.to_json());
+        self
+    }
+
+    fn url(&self, api_version: ApiVersion) -> String {
+        let indexes = join_strings(&self.indexes.iter().map(|s| &s[..]).collect::<Vec<_>>());
+        let doc_types = join_strings(&self.doc_types.iter().map(|s| &s[..]).collect::<Vec<_>>());
+        let endpoint = if api_version >= ApiVersion::V2 { "_delete_by_query" } else { "_query" };
+        if doc_types.is_empty() {
+            format!("{}/{}", indexes, endpoint)
+        } else {
+            format!("{}/{}/{}", indexes, doc_types, endpoint)
+        }
+    }
+
+    pub fn send(&mut self) -> Result<Option<DeleteByQueryResult>, EsError> {
+        let api_version = try!(self.client.api_version());
+        let query = self.query.clone().unwrap_or_else(|| Json::Object(Default::default()));
+        let url = self.url(api_version);
+
+        // Pre-2.0 `_query` takes the query DSL directly as its body; the
+        // post-2.0 `_delete_by_query` endpoint wraps it in a `query` field.
+        let (_, result) = if api_version >= ApiVersion::V2 {
+            let mut body = BTreeMap::new();
+            body.insert("query".to_owned(), query);
+            try!(self.client.post_body_op(&url, &Json::Object(body)))
+        } else {
+            try!(self.client.delete_body_op(&url, &query))
+        };
+        Ok(match result {
+            Some(json) => Some(try!(DeleteByQueryResult::from(&json, api_version))),
+            None       => None
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct DeleteByQueryResult {
+    ok: bool
+}
+
+impl DeleteByQueryResult {
+    fn from(json: &Json, api_version: ApiVersion) -> Result<DeleteByQueryResult, EsError> {
+        // The pre-2.0 `_query` endpoint reports a top-level `ok` boolean.
+        // The post-2.0 `_delete_by_query` endpoint has no such field; it
+        // instead reports per-document problems in a `failures` array, so
+        // success has to be derived from that being empty.
+        let ok = if api_version >= ApiVersion::V2 {
+            json.find("failures")
+                .and_then(|v| v.as_array())
+                .map(|failures| failures.is_empty())
+                .unwrap_or(true)
+        } else {
+            json.find("ok").and_then(|v| v.as_boolean()).unwrap_or(true)
+        };
+        Ok(DeleteByQueryResult { ok: ok })
+    }
+
+    pub fn successful(&self) -> bool {
+        self.ok
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rustc_serialize::json::Json;
+
+    use ::ApiVersion;
+
+    use super::DeleteByQueryResult;
+
+    #[test]
+    fn successful_on_v1_reads_ok_field() {
+        let json = Json::from_str(r#"{"ok": true, "_indices": {}}"#).unwrap();
+        let result = DeleteByQueryResult::from(&json, ApiVersion::V1).unwrap();
+        assert!(result.successful());
+    }
+
+    #[test]
+    fn successful_on_v2_plus_is_true_with_no_failures() {
+        let json = Json::from_str(r#"{"took": 10, "deleted": 3, "batches": 1, "failures": []}"#).unwrap();
+        let result = DeleteByQueryResult::from(&json, ApiVersion::V7).unwrap();
+        assert!(result.successful());
+    }
+
+    #[test]
+    fn unsuccessful_on_v2_plus_with_failures() {
+        let json = Json::from_str(r#"{
+            "took": 10,
+            "deleted": 1,
+            "batches": 1,
+            "failures": [{"index": "i", "type": "_doc", "id": "1", "status": 409}]
+        }"#).unwrap();
+        let result = DeleteByQueryResult::from(&json, ApiVersion::V7).unwrap();
+        assert!(!result.successful());
+    }
+}
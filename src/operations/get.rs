@@ -0,0 +1,90 @@
+/*
+ * Copyright 2015 Ben Ashford
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Implementation of the ElasticSearch Get API, see:
+//! https://www.elastic.co/guide/en/elasticsearch/reference/1.x/docs-get.html
+
+use rustc_serialize::Decodable;
+use rustc_serialize::json::{self, Json};
+
+use ::Client;
+use error::EsError;
+use operations::doc_type_segment;
+use operations::index::find_str;
+
+/// A get-by-id operation
+pub struct GetOperation<'a> {
+    client:   &'a mut Client,
+    index:    &'a str,
+    id:       &'a str,
+    doc_type: Option<&'a str>
+}
+
+impl<'a> GetOperation<'a> {
+    pub fn new(client: &'a mut Client, index: &'a str, id: &'a str) -> GetOperation<'a> {
+        GetOperation {
+            client:   client,
+            index:    index,
+            id:       id,
+            doc_type: None
+        }
+    }
+
+    pub fn with_doc_type(&mut self, doc_type: &'a str) -> &mut Self {
+        self.doc_type = Some(doc_type);
+        self
+    }
+
+    pub fn send(&mut self) -> Result<GetResult, EsError> {
+        let api_version = try!(self.client.api_version());
+        let doc_type = doc_type_segment(api_version, self.doc_type.unwrap_or("_all"));
+        let url = format!("{}/{}/{}", self.index, doc_type, self.id);
+        let (_, result) = try!(self.client.get_op(&url));
+        let result = result.expect("No Json payload");
+        GetResult::from(result)
+    }
+}
+
+#[derive(Debug)]
+pub struct GetResult {
+    pub index:    String,
+    pub doc_type: String,
+    pub id:       String,
+    pub version:  Option<u64>,
+    pub found:    bool,
+    source:       Option<Json>
+}
+
+impl GetResult {
+    fn from(json: Json) -> Result<GetResult, EsError> {
+        Ok(GetResult {
+            index:    try!(find_str(&json, "_index")),
+            doc_type: try!(find_str(&json, "_type")),
+            id:       try!(find_str(&json, "_id")),
+            version:  json.find("_version").and_then(|v| v.as_u64()),
+            found:    json.find("found").and_then(|v| v.as_boolean()).unwrap_or(false),
+            source:   json.find("_source").cloned()
+        })
+    }
+
+    /// Decode the `_source` field of this result into the given type
+    pub fn source<T: Decodable>(&self) -> Result<T, EsError> {
+        match self.source {
+            Some(ref source) => Ok(try!(json::decode(&source.to_string()))),
+            None              => Err(EsError::EsError("No source field in result".to_owned()))
+        }
+    }
+}
@@ -0,0 +1,151 @@
+/*
+ * Copyright 2015 Ben Ashford
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Implementation of the ElasticSearch Index API, see:
+//! https://www.elastic.co/guide/en/elasticsearch/reference/1.x/docs-index_.html
+
+use rustc_serialize::Encodable;
+use rustc_serialize::json::Json;
+
+use ::{ApiVersion, Client};
+use error::EsError;
+use operations::doc_type_segment;
+
+/// The `op_type` parameter of an index operation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OpType {
+    Index,
+    Create
+}
+
+impl OpType {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            OpType::Index  => "index",
+            OpType::Create => "create"
+        }
+    }
+}
+
+/// An index operation, to index (or create) a document of type `E`
+pub struct IndexOperation<'a, 'b, E: Encodable + 'b> {
+    client:   &'a mut Client,
+    index:    &'b str,
+    doc_type: &'b str,
+    id:       Option<&'b str>,
+    ttl:      Option<i64>,
+    op_type:  Option<OpType>,
+    doc:      Option<&'b E>
+}
+
+impl<'a, 'b, E: Encodable + 'b> IndexOperation<'a, 'b, E> {
+    pub fn new(client: &'a mut Client, index: &'b str, doc_type: &'b str) -> IndexOperation<'a, 'b, E> {
+        IndexOperation {
+            client:   client,
+            index:    index,
+            doc_type: doc_type,
+            id:       None,
+            ttl:      None,
+            op_type:  None,
+            doc:      None
+        }
+    }
+
+    pub fn with_doc(&mut self, doc: &'b E) -> &mut Self {
+        self.doc = Some(doc);
+        self
+    }
+
+    pub fn with_id(&mut self, id: &'b str) -> &mut Self {
+        self.id = Some(id);
+        self
+    }
+
+    pub fn with_ttl(&mut self, ttl: i64) -> &mut Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    pub fn with_op_type(&mut self, op_type: OpType) -> &mut Self {
+        self.op_type = Some(op_type);
+        self
+    }
+
+    fn url(&self, api_version: ApiVersion) -> String {
+        let doc_type = doc_type_segment(api_version, self.doc_type);
+        let mut url = match self.id {
+            Some(id) => format!("{}/{}/{}", self.index, doc_type, id),
+            None     => format!("{}/{}", self.index, doc_type)
+        };
+        let mut params: Vec<String> = vec![];
+        // `_ttl` was removed in ElasticSearch 2.0
+        if api_version < ApiVersion::V2 {
+            if let Some(ttl) = self.ttl {
+                params.push(format!("ttl={}", ttl));
+            }
+        }
+        if let Some(op_type) = self.op_type {
+            params.push(format!("op_type={}", op_type.as_str()));
+        }
+        if !params.is_empty() {
+            url = format!("{}?{}", url, params.join("&"));
+        }
+        url
+    }
+
+    pub fn send(&mut self) -> Result<IndexResult, EsError> {
+        let doc = self.doc.expect("No document specified for index operation");
+        let api_version = try!(self.client.api_version());
+        let url = self.url(api_version);
+        let (_, result) = if self.id.is_some() || self.op_type == Some(OpType::Create) {
+            try!(self.client.put_body_op(&url, doc))
+        } else {
+            try!(self.client.post_body_op(&url, doc))
+        };
+        let result = result.expect("No Json payload");
+        IndexResult::from(&result)
+    }
+}
+
+#[derive(Debug)]
+pub struct IndexResult {
+    pub index:    String,
+    pub doc_type: String,
+    pub id:       String,
+    pub version:  u64,
+    pub created:  bool
+}
+
+impl IndexResult {
+    fn from(json: &Json) -> Result<IndexResult, EsError> {
+        Ok(IndexResult {
+            index:    try!(find_str(json, "_index")),
+            doc_type: try!(find_str(json, "_type")),
+            id:       try!(find_str(json, "_id")),
+            version:  json.find("_version").and_then(|v| v.as_u64()).unwrap_or(0),
+            created:  json.find("created").and_then(|v| v.as_boolean()).unwrap_or(false)
+        })
+    }
+}
+
+/// Pulls a required string field out of a `Json` object, or produces an
+/// `EsError` describing what was missing
+pub fn find_str(json: &Json, field: &str) -> Result<String, EsError> {
+    match json.find(field).and_then(|v| v.as_string()) {
+        Some(s) => Ok(s.to_owned()),
+        None    => Err(EsError::EsError(format!("Cannot find '{}' in: {:?}", field, json)))
+    }
+}
@@ -0,0 +1,381 @@
+/*
+ * Copyright 2015 Ben Ashford
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Index and mapping management, see:
+//! https://www.elastic.co/guide/en/elasticsearch/reference/current/indices-create-index.html
+//! https://www.elastic.co/guide/en/elasticsearch/reference/current/indices-put-mapping.html
+
+use std::collections::BTreeMap;
+
+use rustc_serialize::json::{Json, ToJson};
+
+use ::{ApiVersion, Client};
+use error::EsError;
+use operations::doc_type_segment;
+
+/// A set of field mappings, keyed by field name, as used by both
+/// `create_index`'s `mappings` block and `put_mapping`
+pub type Mappings = BTreeMap<String, FieldMapping>;
+
+/// A single field's mapping definition
+#[derive(Debug, Clone)]
+pub struct FieldMapping {
+    json: BTreeMap<String, Json>
+}
+
+impl FieldMapping {
+    fn of_type(type_name: &str) -> FieldMapping {
+        let mut json = BTreeMap::new();
+        json.insert("type".to_owned(), type_name.to_json());
+        FieldMapping { json: json }
+    }
+
+    /// A `keyword` field: exact-match, not analyzed
+    pub fn keyword() -> FieldMapping {
+        FieldMapping::of_type("keyword")
+    }
+
+    /// A `text` field: analyzed for full-text search
+    pub fn text() -> FieldMapping {
+        FieldMapping::of_type("text")
+    }
+
+    /// A `nested` field, with its own set of sub-properties
+    pub fn nested(properties: Mappings) -> FieldMapping {
+        let mut mapping = FieldMapping::of_type("nested");
+        mapping.json.insert("properties".to_owned(), properties.to_json());
+        mapping
+    }
+
+    /// Set the analyzer used to index (and, by default, search) this field
+    pub fn with_analyzer<S: Into<String>>(mut self, analyzer: S) -> FieldMapping {
+        self.json.insert("analyzer".to_owned(), analyzer.into().to_json());
+        self
+    }
+
+    /// Add multi-fields (e.g. a `.keyword` sub-field alongside an analyzed
+    /// `text` field)
+    pub fn with_fields(mut self, fields: Mappings) -> FieldMapping {
+        self.json.insert("fields".to_owned(), fields.to_json());
+        self
+    }
+}
+
+impl ToJson for FieldMapping {
+    fn to_json(&self) -> Json {
+        Json::Object(self.json.clone())
+    }
+}
+
+/// An `edge_ngram` token filter, as used to build autocomplete analyzers
+#[derive(Debug, Clone)]
+pub struct EdgeNGramFilter {
+    min_gram: u32,
+    max_gram: u32
+}
+
+impl EdgeNGramFilter {
+    pub fn new(min_gram: u32, max_gram: u32) -> EdgeNGramFilter {
+        EdgeNGramFilter { min_gram: min_gram, max_gram: max_gram }
+    }
+}
+
+impl ToJson for EdgeNGramFilter {
+    fn to_json(&self) -> Json {
+        let mut d = BTreeMap::new();
+        d.insert("type".to_owned(), "edge_ngram".to_json());
+        d.insert("min_gram".to_owned(), self.min_gram.to_json());
+        d.insert("max_gram".to_owned(), self.max_gram.to_json());
+        Json::Object(d)
+    }
+}
+
+/// A custom analyzer, built from a tokenizer and a chain of token filters
+#[derive(Debug, Clone)]
+pub struct Analyzer {
+    tokenizer: String,
+    filters:   Vec<String>
+}
+
+impl Analyzer {
+    pub fn new<S: Into<String>>(tokenizer: S) -> Analyzer {
+        Analyzer {
+            tokenizer: tokenizer.into(),
+            filters:   vec![]
+        }
+    }
+
+    pub fn with_filter<S: Into<String>>(mut self, filter: S) -> Analyzer {
+        self.filters.push(filter.into());
+        self
+    }
+}
+
+impl ToJson for Analyzer {
+    fn to_json(&self) -> Json {
+        let mut d = BTreeMap::new();
+        d.insert("type".to_owned(), "custom".to_json());
+        d.insert("tokenizer".to_owned(), self.tokenizer.to_json());
+        d.insert("filter".to_owned(), self.filters.to_json());
+        Json::Object(d)
+    }
+}
+
+/// The `settings` block of a `create_index` request
+#[derive(Debug, Clone, Default)]
+pub struct IndexSettings {
+    number_of_shards:   Option<u32>,
+    number_of_replicas: Option<u32>,
+    analyzers:          BTreeMap<String, Analyzer>,
+    filters:            BTreeMap<String, EdgeNGramFilter>
+}
+
+impl IndexSettings {
+    pub fn new() -> IndexSettings {
+        Default::default()
+    }
+
+    pub fn with_number_of_shards(mut self, n: u32) -> IndexSettings {
+        self.number_of_shards = Some(n);
+        self
+    }
+
+    pub fn with_number_of_replicas(mut self, n: u32) -> IndexSettings {
+        self.number_of_replicas = Some(n);
+        self
+    }
+
+    /// Register a custom analyzer under the given name, for use by `text`
+    /// field mappings via `FieldMapping::with_analyzer`
+    pub fn with_analyzer<S: Into<String>>(mut self, name: S, analyzer: Analyzer) -> IndexSettings {
+        self.analyzers.insert(name.into(), analyzer);
+        self
+    }
+
+    /// Register a custom token filter under the given name, for use by
+    /// `Analyzer::with_filter`
+    pub fn with_filter<S: Into<String>>(mut self, name: S, filter: EdgeNGramFilter) -> IndexSettings {
+        self.filters.insert(name.into(), filter);
+        self
+    }
+}
+
+impl ToJson for IndexSettings {
+    fn to_json(&self) -> Json {
+        let mut d = BTreeMap::new();
+        if let Some(n) = self.number_of_shards {
+            d.insert("number_of_shards".to_owned(), n.to_json());
+        }
+        if let Some(n) = self.number_of_replicas {
+            d.insert("number_of_replicas".to_owned(), n.to_json());
+        }
+        if !self.analyzers.is_empty() || !self.filters.is_empty() {
+            let mut analysis = BTreeMap::new();
+            if !self.analyzers.is_empty() {
+                analysis.insert("analyzer".to_owned(), self.analyzers.to_json());
+            }
+            if !self.filters.is_empty() {
+                analysis.insert("filter".to_owned(), self.filters.to_json());
+            }
+            d.insert("analysis".to_owned(), Json::Object(analysis));
+        }
+        Json::Object(d)
+    }
+}
+
+/// Create an index, optionally with custom `settings` and a `mappings` block
+///
+/// See: https://www.elastic.co/guide/en/elasticsearch/reference/current/indices-create-index.html
+pub struct CreateIndexOperation<'a> {
+    client:   &'a mut Client,
+    index:    &'a str,
+    settings: Option<IndexSettings>,
+    doc_type: Option<&'a str>,
+    mappings: Option<Mappings>
+}
+
+impl<'a> CreateIndexOperation<'a> {
+    pub fn new(client: &'a mut Client, index: &'a str) -> CreateIndexOperation<'a> {
+        CreateIndexOperation {
+            client:   client,
+            index:    index,
+            settings: None,
+            doc_type: None,
+            mappings: None
+        }
+    }
+
+    pub fn with_settings(&mut self, settings: IndexSettings) -> &mut Self {
+        self.settings = Some(settings);
+        self
+    }
+
+    /// The type name to nest the `mappings` block's `properties` under on
+    /// pre-7.0 clusters, which still require one; ignored against 7.0+
+    /// clusters, which are typeless. Defaults to `_doc` if not set.
+    pub fn with_doc_type(&mut self, doc_type: &'a str) -> &mut Self {
+        self.doc_type = Some(doc_type);
+        self
+    }
+
+    pub fn with_mappings(&mut self, mappings: Mappings) -> &mut Self {
+        self.mappings = Some(mappings);
+        self
+    }
+
+    fn body(&self, api_version: ApiVersion) -> Json {
+        let mut d = BTreeMap::new();
+        if let Some(ref settings) = self.settings {
+            d.insert("settings".to_owned(), settings.to_json());
+        }
+        if let Some(ref mappings) = self.mappings {
+            let mut properties = BTreeMap::new();
+            properties.insert("properties".to_owned(), mappings.to_json());
+            let mappings_body = if api_version >= ApiVersion::V7 {
+                properties
+            } else {
+                let mut m = BTreeMap::new();
+                m.insert(self.doc_type.unwrap_or("_doc").to_owned(), Json::Object(properties));
+                m
+            };
+            d.insert("mappings".to_owned(), Json::Object(mappings_body));
+        }
+        Json::Object(d)
+    }
+
+    pub fn send(&mut self) -> Result<(), EsError> {
+        let api_version = try!(self.client.api_version());
+        let body = self.body(api_version);
+        let index = self.index;
+        self.client.put_body_op(index, &body).map(|_| ())
+    }
+}
+
+/// Add (or update) the mapping of an existing index
+///
+/// See: https://www.elastic.co/guide/en/elasticsearch/reference/current/indices-put-mapping.html
+pub struct PutMappingOperation<'a> {
+    client:   &'a mut Client,
+    index:    &'a str,
+    doc_type: &'a str,
+    mappings: Mappings
+}
+
+impl<'a> PutMappingOperation<'a> {
+    pub fn new(client: &'a mut Client, index: &'a str, doc_type: &'a str) -> PutMappingOperation<'a> {
+        PutMappingOperation {
+            client:   client,
+            index:    index,
+            doc_type: doc_type,
+            mappings: Mappings::new()
+        }
+    }
+
+    pub fn with_mappings(&mut self, mappings: Mappings) -> &mut Self {
+        self.mappings = mappings;
+        self
+    }
+
+    pub fn send(&mut self) -> Result<(), EsError> {
+        let api_version = try!(self.client.api_version());
+        let doc_type = doc_type_segment(api_version, self.doc_type);
+        let mut body = BTreeMap::new();
+        body.insert("properties".to_owned(), self.mappings.to_json());
+        let url = format!("{}/_mapping/{}", self.index, doc_type);
+        self.client.put_body_op(&url, &Json::Object(body)).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rustc_serialize::json::ToJson;
+
+    use ::{ApiVersion, Client};
+
+    use super::{Analyzer, CreateIndexOperation, EdgeNGramFilter, FieldMapping, IndexSettings};
+
+    #[test]
+    fn field_mapping_keyword_to_json() {
+        let json = FieldMapping::keyword().to_json();
+        assert_eq!(json.find("type").and_then(|v| v.as_string()), Some("keyword"));
+    }
+
+    #[test]
+    fn field_mapping_with_analyzer_to_json() {
+        let json = FieldMapping::text().with_analyzer("my_analyzer").to_json();
+        assert_eq!(json.find("type").and_then(|v| v.as_string()), Some("text"));
+        assert_eq!(json.find("analyzer").and_then(|v| v.as_string()), Some("my_analyzer"));
+    }
+
+    #[test]
+    fn edge_ngram_filter_to_json() {
+        let json = EdgeNGramFilter::new(2, 10).to_json();
+        assert_eq!(json.find("type").and_then(|v| v.as_string()), Some("edge_ngram"));
+        assert_eq!(json.find("min_gram").and_then(|v| v.as_u64()), Some(2));
+        assert_eq!(json.find("max_gram").and_then(|v| v.as_u64()), Some(10));
+    }
+
+    #[test]
+    fn analyzer_to_json_includes_tokenizer_and_filters() {
+        let json = Analyzer::new("standard").with_filter("lowercase").to_json();
+        assert_eq!(json.find("type").and_then(|v| v.as_string()), Some("custom"));
+        assert_eq!(json.find("tokenizer").and_then(|v| v.as_string()), Some("standard"));
+        assert_eq!(json.find("filter").unwrap().as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn index_settings_to_json_omits_analysis_when_empty() {
+        let json = IndexSettings::new().with_number_of_shards(3).to_json();
+        assert_eq!(json.find("number_of_shards").and_then(|v| v.as_u64()), Some(3));
+        assert!(json.find("analysis").is_none());
+    }
+
+    #[test]
+    fn index_settings_to_json_includes_analysis_block() {
+        let json = IndexSettings::new()
+            .with_analyzer("my_analyzer", Analyzer::new("standard"))
+            .with_filter("my_filter", EdgeNGramFilter::new(1, 5))
+            .to_json();
+        let analysis = json.find("analysis").unwrap();
+        assert!(analysis.find("analyzer").unwrap().find("my_analyzer").is_some());
+        assert!(analysis.find("filter").unwrap().find("my_filter").is_some());
+    }
+
+    #[test]
+    fn create_index_body_is_typeless_on_v7_plus() {
+        let mut client = Client::new("localhost", 9200);
+        let mut mappings = super::Mappings::new();
+        mappings.insert("name".to_owned(), FieldMapping::keyword());
+        let mut op = CreateIndexOperation::new(&mut client, "my_index");
+        op.with_mappings(mappings);
+        let body = op.body(ApiVersion::V7);
+        let mappings_body = body.find("mappings").unwrap();
+        assert!(mappings_body.find("properties").is_some());
+    }
+
+    #[test]
+    fn create_index_body_nests_under_doc_type_pre_v7() {
+        let mut client = Client::new("localhost", 9200);
+        let mut mappings = super::Mappings::new();
+        mappings.insert("name".to_owned(), FieldMapping::keyword());
+        let mut op = CreateIndexOperation::new(&mut client, "my_index");
+        op.with_doc_type("my_type").with_mappings(mappings);
+        let body = op.body(ApiVersion::V6);
+        let mappings_body = body.find("mappings").unwrap();
+        assert!(mappings_body.find("properties").is_none());
+        assert!(mappings_body.find("my_type").and_then(|t| t.find("properties")).is_some());
+    }
+}
@@ -0,0 +1,84 @@
+/*
+ * Copyright 2015 Ben Ashford
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Implementations of the various ElasticSearch REST APIs, each exposed as a
+//! method on `Client` returning a builder for that operation.
+
+pub mod alias;
+pub mod analyze;
+pub mod bulk;
+pub mod delete;
+pub mod get;
+pub mod index;
+pub mod indices;
+pub mod search;
+
+use std::collections::BTreeMap;
+
+use rustc_serialize::json::{Json, ToJson};
+
+use ::{ApiVersion, Client};
+use error::EsError;
+use util::join_strings;
+
+/// ElasticSearch 7.0 removed mapping types other than `_doc`; use this to
+/// pick the path segment for a document's type across server versions.
+pub fn doc_type_segment(api_version: ApiVersion, doc_type: &str) -> String {
+    if api_version >= ApiVersion::V7 {
+        "_doc".to_owned()
+    } else {
+        doc_type.to_owned()
+    }
+}
+
+/// Refresh
+///
+/// See: https://www.elastic.co/guide/en/elasticsearch/reference/1.x/indices-refresh.html
+pub struct RefreshOperation<'a> {
+    client:  &'a mut Client,
+    indexes: Vec<String>
+}
+
+impl<'a> RefreshOperation<'a> {
+    pub fn new(client: &'a mut Client) -> RefreshOperation<'a> {
+        RefreshOperation {
+            client:  client,
+            indexes: vec![]
+        }
+    }
+
+    pub fn with_indexes(&mut self, indexes: &[&str]) -> &mut Self {
+        self.indexes = indexes.iter().map(|s| (*s).to_owned()).collect();
+        self
+    }
+
+    pub fn send(&mut self) -> Result<(), EsError> {
+        let url = format!("{}/_refresh", join_strings(&self.indexes.iter()
+                                                        .map(|s| &s[..])
+                                                        .collect::<Vec<_>>()));
+        self.client.post_op(&url).map(|_| ())
+    }
+}
+
+/// Creates an empty `BTreeMap` for a JSON object body, to be filled in
+/// field-by-field with `to_json_field`
+pub fn json_object() -> BTreeMap<String, Json> {
+    BTreeMap::new()
+}
+
+pub fn to_json_field<K: Into<String>, V: ToJson>(map: &mut BTreeMap<String, Json>, key: K, value: V) {
+    map.insert(key.into(), value.to_json());
+}
@@ -0,0 +1,151 @@
+/*
+ * Copyright 2015 Ben Ashford
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Implementation of the ElasticSearch Search APIs (URI and request-body), see:
+//! https://www.elastic.co/guide/en/elasticsearch/reference/1.x/search-uri-request.html
+//! https://www.elastic.co/guide/en/elasticsearch/reference/1.x/search-request-body.html
+
+use rustc_serialize::json::Json;
+
+use ::Client;
+use error::EsError;
+use query::Query;
+use util::join_strings;
+
+/// Search via the `q` query-string parameter
+pub struct SearchURIOperation<'a> {
+    client:  &'a mut Client,
+    indexes: Vec<String>,
+    query:   Option<String>,
+    fields:  Vec<String>
+}
+
+impl<'a> SearchURIOperation<'a> {
+    pub fn new(client: &'a mut Client) -> SearchURIOperation<'a> {
+        SearchURIOperation {
+            client:  client,
+            indexes: vec![],
+            query:   None,
+            fields:  vec![]
+        }
+    }
+
+    pub fn with_indexes(&mut self, indexes: &[&str]) -> &mut Self {
+        self.indexes = indexes.iter().map(|s| (*s).to_owned()).collect();
+        self
+    }
+
+    pub fn with_query(&mut self, query: &str) -> &mut Self {
+        self.query = Some(query.to_owned());
+        self
+    }
+
+    pub fn with_fields(&mut self, fields: &[&str]) -> &mut Self {
+        self.fields = fields.iter().map(|s| (*s).to_owned()).collect();
+        self
+    }
+
+    fn url(&self) -> String {
+        let indexes = join_strings(&self.indexes.iter().map(|s| &s[..]).collect::<Vec<_>>());
+        let mut params: Vec<String> = vec![];
+        if let Some(ref query) = self.query {
+            params.push(format!("q={}", query));
+        }
+        if !self.fields.is_empty() {
+            params.push(format!("fields={}", join_strings(&self.fields.iter().map(|s| &s[..]).collect::<Vec<_>>())));
+        }
+        if params.is_empty() {
+            format!("{}/_search", indexes)
+        } else {
+            format!("{}/_search?{}", indexes, params.join("&"))
+        }
+    }
+
+    pub fn send(&mut self) -> Result<SearchResult, EsError> {
+        let (_, result) = try!(self.client.get_op(&self.url()));
+        let result = result.expect("No Json payload");
+        SearchResult::from(&result)
+    }
+}
+
+/// Search via the Query DSL request body
+pub struct SearchQueryOperation<'a> {
+    client:  &'a mut Client,
+    indexes: Vec<String>,
+    query:   Option<Json>
+}
+
+impl<'a> SearchQueryOperation<'a> {
+    pub fn new(client: &'a mut Client) -> SearchQueryOperation<'a> {
+        SearchQueryOperation {
+            client:  client,
+            indexes: vec![],
+            query:   None
+        }
+    }
+
+    pub fn with_indexes(&mut self, indexes: &[&str]) -> &mut Self {
+        self.indexes = indexes.iter().map(|s| (*s).to_owned()).collect();
+        self
+    }
+
+    pub fn with_query(&mut self, query: &Query) -> &mut Self {
+        self.query = Some(query.to_json());
+        self
+    }
+
+    pub fn send(&mut self) -> Result<SearchResult, EsError> {
+        let indexes = join_strings(&self.indexes.iter().map(|s| &s[..]).collect::<Vec<_>>());
+        let url = format!("{}/_search", indexes);
+        let mut body = ::std::collections::BTreeMap::new();
+        if let Some(ref query) = self.query {
+            body.insert("query".to_owned(), query.clone());
+        }
+        let (_, result) = try!(self.client.post_body_op(&url, &Json::Object(body)));
+        let result = result.expect("No Json payload");
+        SearchResult::from(&result)
+    }
+}
+
+/// The `hits` portion of a search result
+#[derive(Debug)]
+pub struct Hits {
+    pub total: u64,
+    pub hits:  Vec<Json>
+}
+
+/// The result of a search operation
+#[derive(Debug)]
+pub struct SearchResult {
+    pub hits: Hits
+}
+
+impl SearchResult {
+    fn from(json: &Json) -> Result<SearchResult, EsError> {
+        let hits_json = match json.find("hits") {
+            Some(hits_json) => hits_json,
+            None            => return Err(EsError::EsError(format!("No hits field in: {:?}", json)))
+        };
+        let total = hits_json.find("total").and_then(|v| v.as_u64()).unwrap_or(0);
+        let hits = hits_json.find("hits")
+            .and_then(|v| v.as_array())
+            .map(|h| h.clone())
+            .unwrap_or_default();
+        Ok(SearchResult {
+            hits: Hits { total: total, hits: hits }
+        })
+    }
+}
@@ -0,0 +1,172 @@
+/*
+ * Copyright 2015 Ben Ashford
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Implementation of the ElasticSearch Query DSL, see:
+//! https://www.elastic.co/guide/en/elasticsearch/reference/current/query-dsl.html
+
+use std::collections::BTreeMap;
+
+use rustc_serialize::json::{Json, ToJson};
+
+/// A query, as used by the Query DSL (e.g. via `search_query`) and by
+/// operations such as `delete_by_query` that accept a query to select the
+/// documents they act on.
+#[derive(Debug)]
+pub struct Query {
+    json: Json
+}
+
+impl Query {
+    /// A query that matches all documents
+    pub fn build_match_all() -> Query {
+        let mut d = BTreeMap::new();
+        d.insert("match_all".to_owned(), Json::Object(BTreeMap::new()));
+        Query { json: Json::Object(d) }
+    }
+
+    /// A `match` query against a single field
+    pub fn build_match<S: Into<String>, J: ToJson>(field: S, value: J) -> MatchQueryBuilder {
+        MatchQueryBuilder {
+            field:   field.into(),
+            value:   value.to_json(),
+            lenient: None
+        }
+    }
+
+    /// A query wrapping a filter, as used by `filtered` queries
+    pub fn build_filtered(filter: Filter) -> FilteredQueryBuilder {
+        FilteredQueryBuilder { filter: filter }
+    }
+
+    /// Identity method, so that every `build_*` constructor can be followed
+    /// uniformly by `.build()` regardless of whether it returned a builder
+    pub fn build(self) -> Query {
+        self
+    }
+
+    pub fn to_json(&self) -> Json {
+        self.json.clone()
+    }
+}
+
+impl ToJson for Query {
+    fn to_json(&self) -> Json {
+        self.json.clone()
+    }
+}
+
+/// Builder for a `match` query
+pub struct MatchQueryBuilder {
+    field:   String,
+    value:   Json,
+    lenient: Option<bool>
+}
+
+impl MatchQueryBuilder {
+    pub fn with_lenient(mut self, lenient: bool) -> Self {
+        self.lenient = Some(lenient);
+        self
+    }
+
+    pub fn build(self) -> Query {
+        let mut inner = BTreeMap::new();
+        inner.insert("query".to_owned(), self.value);
+        if let Some(lenient) = self.lenient {
+            inner.insert("lenient".to_owned(), Json::Boolean(lenient));
+        }
+        let mut field = BTreeMap::new();
+        field.insert(self.field, Json::Object(inner));
+        let mut d = BTreeMap::new();
+        d.insert("match".to_owned(), Json::Object(field));
+        Query { json: Json::Object(d) }
+    }
+}
+
+/// Builder for a `filtered` query
+pub struct FilteredQueryBuilder {
+    filter: Filter
+}
+
+impl FilteredQueryBuilder {
+    pub fn build(self) -> Query {
+        let mut inner = BTreeMap::new();
+        inner.insert("filter".to_owned(), self.filter.to_json());
+        let mut d = BTreeMap::new();
+        d.insert("filtered".to_owned(), Json::Object(inner));
+        Query { json: Json::Object(d) }
+    }
+}
+
+/// A filter, as used inside `filtered` queries
+#[derive(Debug)]
+pub struct Filter {
+    json: Json
+}
+
+impl Filter {
+    /// A `range` filter against a single field
+    pub fn build_range<S: Into<String>>(field: S) -> RangeFilterBuilder {
+        RangeFilterBuilder {
+            field: field.into(),
+            gte:   None,
+            lte:   None
+        }
+    }
+
+    pub fn to_json(&self) -> Json {
+        self.json.clone()
+    }
+}
+
+impl ToJson for Filter {
+    fn to_json(&self) -> Json {
+        self.json.clone()
+    }
+}
+
+/// Builder for a `range` filter
+pub struct RangeFilterBuilder {
+    field: String,
+    gte:   Option<Json>,
+    lte:   Option<Json>
+}
+
+impl RangeFilterBuilder {
+    pub fn with_gte<J: ToJson>(mut self, val: J) -> Self {
+        self.gte = Some(val.to_json());
+        self
+    }
+
+    pub fn with_lte<J: ToJson>(mut self, val: J) -> Self {
+        self.lte = Some(val.to_json());
+        self
+    }
+
+    pub fn build(self) -> Filter {
+        let mut inner = BTreeMap::new();
+        if let Some(gte) = self.gte {
+            inner.insert("gte".to_owned(), gte);
+        }
+        if let Some(lte) = self.lte {
+            inner.insert("lte".to_owned(), lte);
+        }
+        let mut field = BTreeMap::new();
+        field.insert(self.field, Json::Object(inner));
+        let mut d = BTreeMap::new();
+        d.insert("range".to_owned(), Json::Object(field));
+        Filter { json: Json::Object(d) }
+    }
+}
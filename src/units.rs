@@ -0,0 +1,70 @@
+/*
+ * Copyright 2015 Ben Ashford
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Miscellaneous types used across the various ElasticSearch APIs
+
+use std::fmt;
+
+/// Wraps a value that can be rendered as a URL query-string parameter, e.g.
+/// `true`, `5`, `"field_name"`.
+#[derive(Debug)]
+pub struct OptionVal(pub String);
+
+impl fmt::Display for OptionVal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+macro_rules! from_exp {
+    ($t:ty) => {
+        impl From<$t> for OptionVal {
+            fn from(from: $t) -> OptionVal {
+                OptionVal(format!("{}", from))
+            }
+        }
+    }
+}
+
+from_exp!(bool);
+from_exp!(i64);
+from_exp!(u64);
+
+impl<'a> From<&'a str> for OptionVal {
+    fn from(from: &'a str) -> OptionVal {
+        OptionVal(from.to_owned())
+    }
+}
+
+/// A duration as accepted by ElasticSearch, e.g. `"1m"`, `"30s"`
+#[derive(Debug, Clone)]
+pub struct Duration(String);
+
+impl Duration {
+    pub fn minutes(m: i64) -> Duration {
+        Duration(format!("{}m", m))
+    }
+
+    pub fn seconds(s: i64) -> Duration {
+        Duration(format!("{}s", s))
+    }
+}
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
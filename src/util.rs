@@ -0,0 +1,36 @@
+/*
+ * Copyright 2015 Ben Ashford
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Miscellaneous utilities shared between the various operation builders
+
+/// Defines a `with_x` builder method that sets an `Option<T>` field and
+/// returns `&mut self`. The operation builders in this crate do not use it;
+/// their `with_x` methods are written out by hand.
+#[macro_export]
+macro_rules! add_option {
+    ($n:ident, $f:ident, $t:ty) => {
+        pub fn $n(&mut self, val: $t) -> &mut Self {
+            self.$f = Some(val);
+            self
+        }
+    }
+}
+
+/// Joins a set of strings with commas, as required by many ElasticSearch
+/// URL parameters (e.g. a comma-separated list of indexes)
+pub fn join_strings(parts: &[&str]) -> String {
+    parts.join(",")
+}